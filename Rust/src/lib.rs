@@ -1,8 +1,17 @@
-use std::fs;
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, IsTerminal},
+    str::FromStr,
+};
 
-use rand::{distributions::Standard, prelude::Distribution, Rng};
+use rand::{distributions::Standard, prelude::Distribution, rngs::StdRng, Rng, SeedableRng};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use regex::Regex;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     Up,
     Down,
@@ -14,6 +23,33 @@ pub enum Direction {
     AngledDownLeft,
 }
 
+const ALL_DIRECTIONS: [Direction; 8] = [
+    Direction::Right,
+    Direction::Left,
+    Direction::Down,
+    Direction::Up,
+    Direction::AngledUpRight,
+    Direction::AngledDownLeft,
+    Direction::AngledDownRight,
+    Direction::AngledUpLeft,
+];
+
+impl Direction {
+    /// The (row, column) change of one step in this direction.
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+            Direction::AngledUpRight => (-1, 1),
+            Direction::AngledDownRight => (1, 1),
+            Direction::AngledUpLeft => (-1, -1),
+            Direction::AngledDownLeft => (1, -1),
+        }
+    }
+}
+
 impl std::fmt::Display for Direction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -29,6 +65,8 @@ impl std::fmt::Display for Direction {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Location {
     row: usize,
     column: usize,
@@ -38,6 +76,62 @@ impl Location {
     pub fn new(row: usize, column: usize) -> Self {
         Self { row, column }
     }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The cell `steps` cells away in `dir`, or `None` if that would go above or left of the grid.
+    pub fn step(&self, dir: &Direction, steps: usize) -> Option<Location> {
+        let (row_off, col_off) = dir.delta();
+        let row = self.row as i64 + row_off as i64 * steps as i64;
+        let column = self.column as i64 + col_off as i64 * steps as i64;
+        Some(Location {
+            row: usize::try_from(row).ok()?,
+            column: usize::try_from(column).ok()?,
+        })
+    }
+
+    /// The last cell of a `len`-letter word starting here and going in `dir`.
+    pub fn end(&self, dir: &Direction, len: usize) -> Option<Location> {
+        self.step(dir, len.saturating_sub(1))
+    }
+
+    pub fn manhattan(&self, other: &Location) -> usize {
+        self.row.abs_diff(other.row) + self.column.abs_diff(other.column)
+    }
+
+    pub fn chebyshev(&self, other: &Location) -> usize {
+        self.row
+            .abs_diff(other.row)
+            .max(self.column.abs_diff(other.column))
+    }
+
+    /// The direction that leads from `self` to `other` in a straight line, if any.
+    pub fn direction_to(&self, other: &Location) -> Option<Direction> {
+        let row_diff = other.row as i64 - self.row as i64;
+        let col_diff = other.column as i64 - self.column as i64;
+        if row_diff == 0 && col_diff == 0 {
+            return None;
+        }
+        if row_diff != 0 && col_diff != 0 && row_diff.abs() != col_diff.abs() {
+            return None;
+        }
+        match (row_diff.signum(), col_diff.signum()) {
+            (-1, 0) => Some(Direction::Up),
+            (1, 0) => Some(Direction::Down),
+            (0, -1) => Some(Direction::Left),
+            (0, 1) => Some(Direction::Right),
+            (-1, 1) => Some(Direction::AngledUpRight),
+            (1, 1) => Some(Direction::AngledDownRight),
+            (-1, -1) => Some(Direction::AngledUpLeft),
+            _ => Some(Direction::AngledDownLeft),
+        }
+    }
 }
 
 impl std::fmt::Display for Location {
@@ -46,6 +140,8 @@ impl std::fmt::Display for Location {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     Red,
     Green,
@@ -79,6 +175,25 @@ impl Distribution<Color> for Standard {
     }
 }
 
+impl Color {
+    fn svg_fill(&self) -> &'static str {
+        match self {
+            Color::Red => "#cd3131",
+            Color::Green => "#0dbc79",
+            Color::Yellow => "#e5e510",
+            Color::Magenta => "#bc3fbc",
+            Color::Cyan => "#11a8cd",
+            Color::Reset => "none",
+            Color::LightredEx => "#f14c4c",
+            Color::LightgreenEx => "#23d18b",
+            Color::LightyellowEx => "#f5f543",
+            Color::LightblueEx => "#3b8eea",
+            Color::LightmagentaEx => "#d670d6",
+            Color::LightcyanEx => "#29b8db",
+        }
+    }
+}
+
 impl std::fmt::Display for Color {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let letter = match self {
@@ -99,23 +214,291 @@ impl std::fmt::Display for Color {
     }
 }
 
+/// How `find_word_with` picks a placement when a word occurs more than once. When no placement
+/// fits a rule, the first in reading order of its start cell is used. That is not always the
+/// one `FirstFound` gives, since `find_word` scans rows, then columns, then diagonals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The placement `find_word` reports.
+    FirstFound,
+    /// Prefer placements read right, down, or diagonally up or down to the right.
+    PreferForward,
+    /// Prefer placements read left or right.
+    PreferHorizontal,
+    /// The placement whose start is nearest (Manhattan distance) to the given cell.
+    Closest(Location),
+}
+
+/// Whether `show_solve` uses ANSI colors. Without color, found letters are shown in brackets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    #[default]
+    Always,
+    Never,
+    /// Color only when stdout is a terminal and `NO_COLOR` is not set.
+    Auto,
+}
+
+impl ColorMode {
+    pub fn enabled(&self) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => {
+                io::stdout().is_terminal()
+                    && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HighlightStyle {
+    #[default]
+    Color,
+    /// Show only the found letters, drawing every other cell with the given char (e.g. `'·'`).
+    Dots(char),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridError {
+    Empty,
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    WideCell {
+        row: usize,
+        cell: String,
+    },
+}
+
+impl std::fmt::Display for GridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "The grid has no letters"),
+            GridError::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {row} has {found} letters but the first row has {expected}"
+            ),
+            GridError::WideCell { row, cell } => {
+                write!(
+                    f,
+                    "Row {row} has a cell \"{cell}\" that is not a single letter"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum GenerateError {
+    EmptyGrid,
+    EmptyAlphabet,
+    WordTooLong(String),
+    NoRoom(String),
+}
+
+impl std::fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenerateError::EmptyGrid => write!(f, "The grid needs at least one row and column"),
+            GenerateError::EmptyAlphabet => write!(f, "The filler alphabet has no letters"),
+            GenerateError::WordTooLong(word) => write!(f, "{word} is too long to fit the grid"),
+            GenerateError::NoRoom(word) => write!(
+                f,
+                "Could not place {word} after {PLACEMENT_ATTEMPTS} attempts"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+const PLACEMENT_ATTEMPTS: usize = 1000;
+
+/// A generated puzzle and its solution, which shows only the placed words with '.' elsewhere.
+pub struct Generated {
+    pub puzzle: Grid,
+    pub solution: Grid,
+}
+
+/// Filler letters for `Grid::generate_with_alphabet`.
+pub const ALPHABET_EN: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub const ALPHABET_ES: &str = "ABCDEFGHIJKLMNÑOPQRSTUVWXYZ";
+pub const ALPHABET_DE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZÄÖÜß";
+
+const SVG_CELL: usize = 30;
+
+#[derive(Debug, Default)]
+pub struct SolveReport {
+    pub found: Vec<(String, Location, Direction)>,
+    /// The color each found word was highlighted in, matching `found` by index.
+    pub colors: Vec<Color>,
+    pub not_found: Vec<String>,
+}
+
+impl SolveReport {
+    pub fn found_count(&self) -> usize {
+        self.found.len()
+    }
+
+    pub fn total(&self) -> usize {
+        self.found.len() + self.not_found.len()
+    }
+
+    /// A key for the solved grid: one line per found word, written in its color when `mode`
+    /// allows, with where it starts and which way it runs.
+    pub fn legend(&self, mode: ColorMode) -> String {
+        let use_color = mode.enabled();
+        let mut out = String::new();
+        for ((word, start, dir), color) in self.found.iter().zip(&self.colors) {
+            if use_color {
+                out.push_str(&format!(
+                    "{color}{word}{} at {start} going {dir}.\n",
+                    Color::Reset
+                ));
+            } else {
+                out.push_str(&format!("{word} at {start} going {dir}.\n"));
+            }
+        }
+        out
+    }
+
+    /// The found words sorted by where they start, row by row, as a person reads the grid.
+    pub fn in_reading_order(&self) -> Vec<&(String, Location, Direction)> {
+        let mut found: Vec<_> = self.found.iter().collect();
+        found.sort_by_key(|(_, start, _)| *start);
+        found
+    }
+
+    /// The top-left and bottom-right corners of the smallest box holding every found word, or
+    /// `None` if nothing was found.
+    pub fn bounding_box(&self) -> Option<(Location, Location)> {
+        let ends = self.found.iter().flat_map(|(word, start, dir)| {
+            [Some(*start), start.end(dir, word.chars().count())]
+                .into_iter()
+                .flatten()
+        });
+        ends.fold(None, |corners, cell| match corners {
+            None => Some((cell, cell)),
+            Some((top_left, bottom_right)) => Some((
+                Location::new(top_left.row.min(cell.row), top_left.column.min(cell.column)),
+                Location::new(
+                    bottom_right.row.max(cell.row),
+                    bottom_right.column.max(cell.column),
+                ),
+            )),
+        })
+    }
+}
+
+/// Where a word starts and which way it runs.
+pub type Placement = (Location, Direction);
+
+/// One result from `Grid::solve_stream`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveEvent {
+    Found(String, Location, Direction),
+    Missing(String),
+}
+
+/// What changed between two solutions, from `diff_solutions`.
+#[derive(Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SolutionDiff {
+    pub added: Vec<(String, Location, Direction)>,
+    pub removed: Vec<(String, Location, Direction)>,
+    /// Each word found in both, with its placement before and after.
+    pub moved: Vec<(String, Placement, Placement)>,
+}
+
+impl SolutionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+impl std::fmt::Display for SolutionDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (word, start, dir) in &self.added {
+            writeln!(f, "+ {word} at {start} going {dir}")?;
+        }
+        for (word, start, dir) in &self.removed {
+            writeln!(f, "- {word} at {start} going {dir}")?;
+        }
+        for (word, (old_start, old_dir), (new_start, new_dir)) in &self.moved {
+            writeln!(
+                f,
+                "~ {word} moved from {old_start} going {old_dir} to {new_start} going {new_dir}"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The seed `solve_report` and the `finder` binary pass to `assign_colors`.
+pub const DEFAULT_COLOR_SEED: u64 = 0;
+/// The colors `solve_with_distinct_colors` hands out, plain and light shades alternating so that
+/// words next to each other in the list differ the most.
+pub const PALETTE: [Color; 11] = [
+    Color::Red,
+    Color::LightgreenEx,
+    Color::Magenta,
+    Color::LightyellowEx,
+    Color::Cyan,
+    Color::LightredEx,
+    Color::Green,
+    Color::LightblueEx,
+    Color::Yellow,
+    Color::LightmagentaEx,
+    Color::LightcyanEx,
+];
+const UNDERLINE: &str = "\x1b[4m";
+
 pub struct Grid {
     rows: Vec<Vec<char>>,
     columns: Vec<Vec<char>>,
     diag_up_right: Vec<Vec<char>>,
     diag_down_right: Vec<Vec<char>>,
-    highlighted: Vec<Vec<String>>,
+    marks: Vec<Vec<Vec<Color>>>,
+    color_mode: ColorMode,
+    wrap: bool,
 }
 
 impl Grid {
-    pub fn new(text: Vec<&str>) -> Self {
+    pub fn try_new(text: Vec<&str>) -> Result<Self, GridError> {
+        let width = match text.first() {
+            Some(line) if !line.is_empty() => line.chars().count(),
+            _ => return Err(GridError::Empty),
+        };
+        for (row, line) in text.iter().enumerate() {
+            let found = line.chars().count();
+            if found != width {
+                return Err(GridError::Ragged {
+                    row,
+                    expected: width,
+                    found,
+                });
+            }
+        }
+        Ok(Grid::new(text))
+    }
+
+    fn new(text: Vec<&str>) -> Self {
         let n_rows = text.len();
-        let n_cols = text[0].len();
+        let n_cols = text[0].chars().count();
         let mut rows = vec![Vec::new(); n_rows];
         let mut columns = vec![Vec::new(); n_cols];
         let mut diag_up_right = vec![Vec::new(); n_rows + n_cols - 1];
         let mut diag_down_right = vec![Vec::new(); n_rows + n_cols - 1];
-        let mut highlighted = vec![Vec::new(); n_rows];
 
         for (row, line) in text.iter().enumerate() {
             for (col, letter) in line.chars().enumerate() {
@@ -123,7 +506,6 @@ impl Grid {
                 columns[col].push(letter);
                 diag_up_right[row + col].push(letter);
                 diag_down_right[n_rows + col - row - 1].push(letter);
-                highlighted[row].push(letter.to_string());
             }
         }
         for row in &mut diag_up_right {
@@ -135,118 +517,707 @@ impl Grid {
             columns,
             diag_up_right,
             diag_down_right,
-            highlighted,
+            marks: vec![vec![Vec::new(); n_cols]; n_rows],
+            color_mode: ColorMode::default(),
+            wrap: false,
         }
     }
-    pub fn from_str(text: &str) -> Self {
-        let text = text.to_string().replace(" ", "");
-        let text: Vec<&str> = text.split("\r\n").collect();
-        Grid::new(text)
+    /// Splits each row into cells on `sep`, skipping empty cells, so that
+    /// `Grid::from_str_with(&grid.to_string(), Some(' '))` gives back the same grid. With `None`
+    /// every char is a cell, spaces included.
+    pub fn from_str_with(text: &str, sep: Option<char>) -> Result<Self, GridError> {
+        let mut rows = Vec::new();
+        for (row, line) in text.lines().enumerate() {
+            let Some(sep) = sep else {
+                rows.push(line.to_string());
+                continue;
+            };
+            let mut cells = String::new();
+            for cell in line.split(sep).filter(|cell| !cell.is_empty()) {
+                let mut letters = cell.chars();
+                match (letters.next(), letters.next()) {
+                    (Some(letter), None) => cells.push(letter),
+                    _ => {
+                        return Err(GridError::WideCell {
+                            row,
+                            cell: cell.to_string(),
+                        })
+                    }
+                }
+            }
+            rows.push(cells);
+        }
+        Grid::try_new(rows.iter().map(|line| line.as_str()).collect())
+    }
+
+    /// Reads only the grid from a puzzle file; a word list after it, if any, is ignored.
+    pub fn from_file(path: &str) -> Result<Self, ReadError> {
+        read_file(path).map(|(grid, _)| grid)
+    }
+
+    /// Places each word in a random direction, then fills the rest with random letters A-Z.
+    pub fn generate(
+        words: &[&str],
+        rows: usize,
+        cols: usize,
+        rng: &mut impl Rng,
+    ) -> Result<Generated, GenerateError> {
+        Grid::generate_with_alphabet(words, rows, cols, ALPHABET_EN, rng)
+    }
+
+    /// Like `generate`, but the filler letters are drawn evenly from `alphabet`, e.g.
+    /// `ALPHABET_ES` for a Spanish puzzle.
+    pub fn generate_with_alphabet(
+        words: &[&str],
+        rows: usize,
+        cols: usize,
+        alphabet: &str,
+        rng: &mut impl Rng,
+    ) -> Result<Generated, GenerateError> {
+        let alphabet: Vec<char> = alphabet.chars().collect();
+        if alphabet.is_empty() {
+            return Err(GenerateError::EmptyAlphabet);
+        }
+        let mut layout = place_words(words, rows, cols, rng)?;
+        let solution = grid_from_layout(&layout, '.');
+        for cell in layout.iter_mut().flatten() {
+            cell.get_or_insert_with(|| alphabet[rng.gen_range(0..alphabet.len())]);
+        }
+        Ok(Generated {
+            puzzle: grid_from_layout(&layout, '.'),
+            solution,
+        })
+    }
+
+    /// The letter at `loc`, or `None` if it is outside the grid.
+    pub fn get(&self, loc: &Location) -> Option<char> {
+        self.rows.get(loc.row)?.get(loc.column).copied()
+    }
+
+    /// The number of (rows, columns).
+    pub fn dimensions(&self) -> (usize, usize) {
+        (
+            self.rows.len(),
+            self.rows.first().map_or(0, |line| line.len()),
+        )
+    }
+
+    /// Pads with `fill` or truncates to the new size. Any highlighting is cleared. A size with no
+    /// rows or no columns is an error and leaves the grid as it was.
+    pub fn resize(
+        &mut self,
+        new_rows: usize,
+        new_cols: usize,
+        fill: char,
+    ) -> Result<(), GridError> {
+        let text: Vec<String> = (0..new_rows)
+            .map(|row| {
+                (0..new_cols)
+                    .map(|column| self.get(&Location { row, column }).unwrap_or(fill))
+                    .collect()
+            })
+            .collect();
+        let mut resized = Grid::try_new(text.iter().map(|line| line.as_str()).collect())?;
+        resized.color_mode = self.color_mode;
+        resized.wrap = self.wrap;
+        *self = resized;
+        Ok(())
     }
 
     pub fn show_grid(&self) {
-        for line in &self.rows {
-            for letter in line {
-                print!("{} ", letter)
+        print!("{}", self.grid_string());
+    }
+
+    pub fn grid_string(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn show_solve(&self) {
+        print!("{}", self.solve_string());
+    }
+
+    pub fn show_solve_with(&self, style: &HighlightStyle) {
+        print!("{}", self.solve_string_with(style));
+    }
+
+    pub fn solve_string(&self) -> String {
+        self.solve_string_with(&HighlightStyle::Color)
+    }
+
+    pub fn solve_string_with(&self, style: &HighlightStyle) -> String {
+        let use_color = self.color_mode.enabled();
+        let mut out = String::new();
+        for (row, line) in self.rows.iter().enumerate() {
+            for (col, letter) in line.iter().enumerate() {
+                let plain = match style {
+                    HighlightStyle::Dots(dot) if !self.is_highlighted(row, col) => *dot,
+                    _ => *letter,
+                };
+                let marks = &self.marks[row][col];
+                let cell = if use_color && marks.len() > 1 {
+                    format!("{}{}{}{} ", marks[0], UNDERLINE, letter, Color::Reset)
+                } else if use_color && !marks.is_empty() {
+                    format!("{}{}{} ", marks[0], letter, Color::Reset)
+                } else if use_color {
+                    format!("{} ", plain)
+                } else if self.is_highlighted(row, col) {
+                    format!("[{}] ", plain)
+                } else {
+                    format!(" {}  ", plain)
+                };
+                out.push_str(&cell);
             }
-            print!("\n");
+            out.push('\n');
         }
+        out
     }
 
-    pub fn show_solve(&self) {
-        for line in &self.highlighted {
-            for letter in line.iter() {
-                print!("{} ", letter)
+    pub fn set_color_mode(&mut self, mode: ColorMode) {
+        self.color_mode = mode;
+    }
+
+    /// Lets every search continue a word past the end of a row or column back to its start, as
+    /// long as the word does not use a cell twice. Diagonals never wrap.
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    fn is_highlighted(&self, row: usize, col: usize) -> bool {
+        !self.marks[row][col].is_empty()
+    }
+
+    /// The color of each highlight covering the cell, oldest first, with an entry per highlight
+    /// even when two words share a color. Cells where words cross have more than one;
+    /// `show_solve` draws them in the first color, underlined.
+    pub fn colors_at(&self, loc: &Location) -> &[Color] {
+        self.marks
+            .get(loc.row)
+            .and_then(|line| line.get(loc.column))
+            .map_or(&[], |marks| marks.as_slice())
+    }
+
+    /// A standalone SVG of the grid, with highlighted cells filled in their color.
+    pub fn to_svg(&self) -> String {
+        let height = self.rows.len() * SVG_CELL;
+        let width = self.rows.first().map_or(0, |line| line.len()) * SVG_CELL;
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\">\n\
+             <rect width=\"{width}\" height=\"{height}\" fill=\"white\"/>\n"
+        );
+        for (row, line) in self.rows.iter().enumerate() {
+            for (col, letter) in line.iter().enumerate() {
+                let (x, y) = (col * SVG_CELL, row * SVG_CELL);
+                if let Some(color) = self.marks[row][col].first() {
+                    svg.push_str(&format!(
+                        "<rect x=\"{x}\" y=\"{y}\" width=\"{SVG_CELL}\" height=\"{SVG_CELL}\" \
+                         fill=\"{}\"/>\n",
+                        color.svg_fill()
+                    ));
+                }
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" \
+                     text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                    x + SVG_CELL / 2,
+                    y + SVG_CELL / 2,
+                    SVG_CELL * 2 / 3,
+                    xml_escape(*letter)
+                ));
             }
-            print!("\n");
         }
+        svg.push_str("</svg>\n");
+        svg
     }
 
-    fn highlight(&mut self, start: &Location, dir: &Direction, len: usize, color: &Color) {
-        let (row_off, col_off) = match dir {
-            Direction::Up => (-1, 0),
-            Direction::Down => (1, 0),
-            Direction::Left => (0, -1),
-            Direction::Right => (0, 1),
-            Direction::AngledUpRight => (-1, 1),
-            Direction::AngledDownRight => (1, 1),
-            Direction::AngledUpLeft => (-1, -1),
-            Direction::AngledDownLeft => (1, -1),
+    /// Highlights a `len`-letter word at a known placement, e.g. one from `solve_many`.
+    /// Returns false, highlighting nothing, if the word runs off the grid.
+    pub fn highlight(
+        &mut self,
+        start: &Location,
+        dir: &Direction,
+        len: usize,
+        color: &Color,
+    ) -> bool {
+        self.highlight_stride(start, dir, len, 1, color)
+    }
+
+    fn highlight_stride(
+        &mut self,
+        start: &Location,
+        dir: &Direction,
+        len: usize,
+        stride: usize,
+        color: &Color,
+    ) -> bool {
+        let Some(cells) = self.cells(start, dir, len, stride) else {
+            return false;
+        };
+        for (row, col) in cells {
+            self.paint(row, col, color);
+        }
+        true
+    }
+
+    /// The (row, column) of each letter, or `None` if one falls outside the grid. In wrap mode a
+    /// row or column word continues from the opposite edge instead, unless it would reach a cell
+    /// twice.
+    fn cells(
+        &self,
+        start: &Location,
+        dir: &Direction,
+        len: usize,
+        stride: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        self.get(start)?;
+        let (n_rows, n_cols) = self.dimensions();
+        let (row_off, col_off) = dir.delta();
+        let span = len.saturating_sub(1) * stride;
+        let wraps = self.wrap
+            && match (row_off, col_off) {
+                (0, _) => span < n_cols,
+                (_, 0) => span < n_rows,
+                _ => false,
+            };
+        (0..len)
+            .map(|idx| {
+                let cell = if wraps {
+                    let step = (idx * stride) as i64;
+                    let wrap = |pos: usize, off: i32, size: usize| {
+                        (pos as i64 + step * off as i64).rem_euclid(size as i64) as usize
+                    };
+                    Location {
+                        row: wrap(start.row, row_off, n_rows),
+                        column: wrap(start.column, col_off, n_cols),
+                    }
+                } else {
+                    start.step(dir, idx * stride)?
+                };
+                self.get(&cell)?;
+                Some((cell.row, cell.column))
+            })
+            .collect()
+    }
+
+    fn paint(&mut self, row: usize, col: usize, color: &Color) {
+        self.marks[row][col].push(*color);
+    }
+
+    pub fn find_horizontal_forward(&self, word: &str) -> Option<(Location, Direction)> {
+        let target: Vec<char> = word.chars().collect();
+        if target.is_empty() {
+            return None;
+        }
+        for (row, group) in self.rows.iter().enumerate() {
+            if let Some(column) = window_starts(group.len(), target.len(), self.wrap)
+                .find(|&pos| window_matches(group, pos, &target, false))
+            {
+                return Some((Location { row, column }, Direction::Right));
+            }
+        }
+        None
+    }
+
+    pub fn find_anagram(&self, word: &str) -> Vec<(Location, Direction)> {
+        let mut target: Vec<char> = word.chars().collect();
+        target.sort_unstable();
+        let mut found = Vec::new();
+        if target.is_empty() {
+            return found;
+        }
+        // A reversed window holds the same letters, so only forward directions are scanned.
+        let directions = [
+            Direction::Right,
+            Direction::Down,
+            Direction::AngledUpRight,
+            Direction::AngledDownRight,
+        ];
+        // A single letter covers the same cell in every direction.
+        let directions = if target.len() == 1 {
+            &directions[..1]
+        } else {
+            &directions[..]
         };
-        for idx in 0..len {
-            let row = (start.row as i32 + idx as i32 * row_off) as usize;
-            let col = (start.column as i32 + idx as i32 * col_off) as usize;
-            let letter = self.rows[row][col];
-            self.highlighted[row][col] = format!("{}{}{}", color, letter, Color::Reset);
+        for row in 0..self.rows.len() {
+            for column in 0..self.rows[row].len() {
+                let start = Location { row, column };
+                for &dir in directions {
+                    if let Some(mut letters) = self.letters_from(&start, &dir, target.len(), 1) {
+                        letters.sort_unstable();
+                        if letters == target {
+                            found.push((start, dir));
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// Every banned word hidden in the grid, sorted by word, with the grid's own letters for it.
+    /// Letter case is ignored, so a lowercase list still catches an uppercase grid.
+    pub fn scan_banned(
+        &self,
+        banned: &HashSet<String>,
+    ) -> Vec<(String, String, Location, Direction)> {
+        let mut words: Vec<&String> = banned.iter().collect();
+        words.sort();
+        words
+            .into_iter()
+            .filter_map(|word| {
+                let (loc, dir) = self.search(word, true)?;
+                let text = self.text_at(&loc, &dir, word.chars().count())?;
+                Some((word.clone(), text, loc, dir))
+            })
+            .collect()
+    }
+
+    /// The `len` letters of the grid starting at `start` and running in `dir`, or `None` if they
+    /// would run off the grid.
+    pub fn text_at(&self, start: &Location, dir: &Direction, len: usize) -> Option<String> {
+        Some(self.letters_from(start, dir, len, 1)?.into_iter().collect())
+    }
+
+    pub fn is_fully_solvable(&self, words: &[&str]) -> bool {
+        words
+            .iter()
+            .all(|word| self.first_placement(word).is_some())
+    }
+
+    /// How many distinct places `word` can be found. A placement read in both directions
+    /// (a palindrome) counts once.
+    pub fn count_occurrences(&self, word: &str) -> usize {
+        self.distinct_placements(word).len()
+    }
+
+    /// The words that are missing or can be found in more than one place, with their count.
+    pub fn validate_unique(&self, words: &[String]) -> Vec<(String, usize)> {
+        words
+            .iter()
+            .map(|word| (word.clone(), self.count_occurrences(word)))
+            .filter(|(_, count)| *count != 1)
+            .collect()
+    }
+
+    fn first_placement(&self, word: &str) -> Option<(Location, Direction)> {
+        self.placements(word).next()
+    }
+
+    /// Every placement of `word`, in reading order of the start cell.
+    fn distinct_placements(&self, word: &str) -> Vec<(Location, Direction)> {
+        let len = word.chars().count();
+        let mut seen: Vec<Vec<(usize, usize)>> = Vec::new();
+        let mut found = Vec::new();
+        for (start, dir) in self.placements(word) {
+            let Some(mut cells) = self.cells(&start, &dir, len, 1) else {
+                continue;
+            };
+            cells.sort();
+            if !seen.contains(&cells) {
+                seen.push(cells);
+                found.push((start, dir));
+            }
+        }
+        found
+    }
+
+    fn placements<'a>(&'a self, word: &str) -> impl Iterator<Item = (Location, Direction)> + 'a {
+        let target: Vec<char> = word.chars().collect();
+        let starts =
+            self.rows.iter().enumerate().flat_map(|(row, line)| {
+                (0..line.len()).map(move |column| Location { row, column })
+            });
+        starts
+            .flat_map(|start| ALL_DIRECTIONS.iter().map(move |dir| (start, *dir)))
+            .filter(move |(start, dir)| {
+                !target.is_empty()
+                    && self.letters_from(start, dir, target.len(), 1).as_ref() == Some(&target)
+            })
+    }
+
+    /// `len` letters starting at `start`, taking every `stride`-th cell in `dir`.
+    fn letters_from(
+        &self,
+        start: &Location,
+        dir: &Direction,
+        len: usize,
+        stride: usize,
+    ) -> Option<Vec<char>> {
+        let cells = self.cells(start, dir, len, stride)?;
+        Some(
+            cells
+                .into_iter()
+                .map(|(row, col)| self.rows[row][col])
+                .collect(),
+        )
+    }
+
+    /// Every placement of `word`, each highlighted. A placement read in both directions
+    /// (a palindrome) is only reported once.
+    pub fn find_all(&mut self, word: &str, color: &Color) -> Vec<(Location, Direction)> {
+        let len = word.chars().count();
+        let found = self.distinct_placements(word);
+        for (start, dir) in &found {
+            self.highlight(start, dir, len, color);
+        }
+        found
+    }
+
+    /// Finds a word whose letters are `stride` cells apart, e.g. every other letter for a
+    /// stride of 2. A stride of 1 is the same as `find_word`.
+    pub fn find_word_stride(
+        &mut self,
+        word: &str,
+        stride: usize,
+        color: &Color,
+    ) -> Option<(Location, Direction)> {
+        if stride == 1 {
+            return self.find_word(word, color);
         }
+        let target: Vec<char> = word.chars().collect();
+        if target.is_empty() || stride == 0 {
+            return None;
+        }
+        let (n_rows, n_cols) = self.dimensions();
+        let found = (0..n_rows)
+            .flat_map(|row| (0..n_cols).map(move |column| Location { row, column }))
+            .flat_map(|start| ALL_DIRECTIONS.iter().map(move |dir| (start, *dir)))
+            .find(|(start, dir)| {
+                self.letters_from(start, dir, target.len(), stride).as_ref() == Some(&target)
+            })?;
+        self.highlight_stride(&found.0, &found.1, target.len(), stride, color);
+        Some(found)
+    }
+
+    pub fn find_word_with(
+        &mut self,
+        word: &str,
+        color: &Color,
+        tie_breaker: &TieBreak,
+    ) -> Option<(Location, Direction)> {
+        if let TieBreak::FirstFound = tie_breaker {
+            return self.find_word(word, color);
+        }
+        let candidates: Vec<(Location, Direction)> = self.placements(word).collect();
+        let chosen = match tie_breaker {
+            TieBreak::PreferForward => candidates.iter().find(|(_, dir)| is_forward(dir)),
+            TieBreak::PreferHorizontal => candidates
+                .iter()
+                .find(|(_, dir)| matches!(dir, Direction::Left | Direction::Right)),
+            TieBreak::Closest(target) => candidates
+                .iter()
+                .min_by_key(|(start, _)| start.manhattan(target)),
+            TieBreak::FirstFound => None,
+        }
+        .or(candidates.first())
+        .copied();
+        if let Some((start, dir)) = &chosen {
+            self.highlight(start, dir, word.chars().count(), color);
+        }
+        chosen
     }
+
     pub fn find_word(&mut self, word: &str, color: &Color) -> Option<(Location, Direction)> {
+        let found = self.locate(word)?;
+        self.highlight(&found.0, &found.1, word.chars().count(), color);
+        Some(found)
+    }
+
+    /// Like `find_word`, but ignores letter case. The original letters are highlighted and
+    /// returned as they appear in the grid, e.g. "APPLE" for "apple".
+    pub fn find_word_ci(
+        &mut self,
+        word: &str,
+        color: &Color,
+    ) -> Option<(Location, Direction, String)> {
+        let (start, dir) = self.search(word, true)?;
+        let len = word.chars().count();
+        let text = self.text_at(&start, &dir, len)?;
+        self.highlight(&start, &dir, len, color);
+        Some((start, dir, text))
+    }
+
+    /// Locates every word, in parallel with the `rayon` feature. Results are in the same
+    /// order as `words`; nothing is highlighted.
+    pub fn solve_many(&self, words: &[String]) -> Vec<(String, Option<(Location, Direction)>)> {
+        #[cfg(feature = "rayon")]
+        let words = words.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let words = words.iter();
+        words
+            .map(|word| (word.clone(), self.locate(word)))
+            .collect()
+    }
+
+    /// Locates each word as the iterator is advanced, so the first results are ready before
+    /// the whole list is searched. Nothing is highlighted.
+    pub fn solve_stream<'a, I>(&'a self, words: I) -> impl Iterator<Item = SolveEvent> + 'a
+    where
+        I: IntoIterator<Item = String>,
+        I::IntoIter: 'a,
+    {
+        words.into_iter().map(|word| match self.locate(&word) {
+            Some((loc, dir)) => SolveEvent::Found(word, loc, dir),
+            None => SolveEvent::Missing(word),
+        })
+    }
+
+    /// Solves and highlights every word. Colors come from `assign_colors` with
+    /// `DEFAULT_COLOR_SEED`, so the same words are always drawn the same way.
+    pub fn solve_report(&mut self, words: &[String]) -> SolveReport {
+        self.solve_report_seeded(words, DEFAULT_COLOR_SEED)
+    }
+
+    /// Like `solve_report`, but with the colors `assign_colors(words, seed)` gives.
+    pub fn solve_report_seeded(&mut self, words: &[String], seed: u64) -> SolveReport {
+        self.solve_report_by(words, seed, |_| 0)
+    }
+
+    /// Like `solve_report`, but words are highlighted from the highest `overlap_priority` to
+    /// the lowest, and `found` lists them in that order. Where words cross, the cell is drawn in
+    /// the color of the first word highlighted there, so the highest priority owns it. Words
+    /// with equal priority keep list order, which is what `solve_report` uses for all of them.
+    pub fn solve_report_with_priority(
+        &mut self,
+        words: &[String],
+        overlap_priority: impl Fn(&(String, Location, Direction)) -> i32,
+    ) -> SolveReport {
+        self.solve_report_by(words, DEFAULT_COLOR_SEED, overlap_priority)
+    }
+
+    fn solve_report_by(
+        &mut self,
+        words: &[String],
+        seed: u64,
+        overlap_priority: impl Fn(&(String, Location, Direction)) -> i32,
+    ) -> SolveReport {
+        let colors = assign_colors(words, seed);
+        let mut report = SolveReport::default();
+        let mut found = Vec::new();
+        for ((word, placement), color) in self.solve_many(words).into_iter().zip(colors) {
+            match placement {
+                Some((start, dir)) => found.push(((word, start, dir), color)),
+                None => report.not_found.push(word),
+            }
+        }
+        found.sort_by_key(|(entry, _)| std::cmp::Reverse(overlap_priority(entry)));
+        for ((word, start, dir), color) in found {
+            self.highlight(&start, &dir, word.chars().count(), &color);
+            report.found.push((word, start, dir));
+            report.colors.push(color);
+        }
+        report
+    }
+
+    /// Solves and highlights every word, giving each a color that no highlighted word touching
+    /// or crossing it already has. Colors are handed out in turn from `PALETTE`; once every color
+    /// is taken around a word it gets the next one in turn anyway. Returns the color of each word
+    /// found, in the order of `words`.
+    pub fn solve_with_distinct_colors(&mut self, words: &[String]) -> Vec<(String, Color)> {
+        let mut next = 0;
+        let mut chosen = Vec::new();
+        for (word, found) in self.solve_many(words) {
+            let Some(cells) =
+                found.and_then(|(start, dir)| self.cells(&start, &dir, word.chars().count(), 1))
+            else {
+                continue;
+            };
+            let nearby = self.colors_near(&cells);
+            let pick = (0..PALETTE.len())
+                .map(|offset| (next + offset) % PALETTE.len())
+                .find(|idx| !nearby.contains(&PALETTE[*idx]))
+                .unwrap_or(next);
+            next = (pick + 1) % PALETTE.len();
+            for (row, col) in cells {
+                self.paint(row, col, &PALETTE[pick]);
+            }
+            chosen.push((word, PALETTE[pick]));
+        }
+        chosen
+    }
+
+    fn colors_near(&self, cells: &[(usize, usize)]) -> Vec<Color> {
+        let mut nearby = Vec::new();
+        for &(row, col) in cells {
+            for near_row in row.saturating_sub(1)..=row + 1 {
+                for near_col in col.saturating_sub(1)..=col + 1 {
+                    let near = Location::new(near_row, near_col);
+                    for color in self.colors_at(&near) {
+                        if !nearby.contains(color) {
+                            nearby.push(*color);
+                        }
+                    }
+                }
+            }
+        }
+        nearby
+    }
+
+    /// Where `find_word` would find the word, without highlighting it.
+    pub fn locate(&self, word: &str) -> Option<(Location, Direction)> {
+        self.search(word, false)
+    }
+
+    fn search(&self, word: &str, case_insensitive: bool) -> Option<(Location, Direction)> {
+        let letters: Vec<char> = word.chars().collect();
+        let reversed: Vec<char> = letters.iter().rev().copied().collect();
         for (row, group) in self.rows.iter().enumerate() {
-            if let Some((column, to_right)) = find_in_group(word, group) {
+            if let Some((column, to_right)) =
+                find_in_group(&letters, &reversed, group, self.wrap, case_insensitive)
+            {
                 let dir = if to_right {
                     Direction::Right
                 } else {
                     Direction::Left
                 };
-                let start = Location { row, column };
-                self.highlight(&start, &dir, word.len(), color);
-                return Some((start, dir));
+                let column = column % group.len();
+                return Some((Location { row, column }, dir));
             }
         }
         for (column, group) in self.columns.iter().enumerate() {
-            if let Some((row, is_down)) = find_in_group(word, group) {
+            if let Some((row, is_down)) =
+                find_in_group(&letters, &reversed, group, self.wrap, case_insensitive)
+            {
                 let dir = if is_down {
                     Direction::Down
                 } else {
                     Direction::Up
                 };
-                let start = Location { row, column };
-                self.highlight(&start, &dir, word.len(), color);
-                return Some((start, dir));
+                let row = row % group.len();
+                return Some((Location { row, column }, dir));
             }
         }
 
-        let num_rows = self.rows.len();
+        let last_row = self.rows.len() - 1;
+        // diag_up_right[diag] holds the cells where row + column == diag, starting from the
+        // bottom-most one.
         for (diag, group) in self.diag_up_right.iter().enumerate() {
-            if let Some((idx, is_forward)) = find_in_group(word, group) {
+            if let Some((idx, is_forward)) =
+                find_in_group(&letters, &reversed, group, false, case_insensitive)
+            {
                 let dir = if is_forward {
                     Direction::AngledUpRight
                 } else {
                     Direction::AngledDownLeft
                 };
-                let row: usize;
-                let column: usize;
-                if diag < num_rows {
-                    row = num_rows - diag;
-                    column = idx;
-                } else {
-                    row = num_rows - idx - 1;
-                    column = (diag - num_rows) + idx + 1;
-                }
-                let start = Location { row, column };
-                self.highlight(&start, &dir, word.len(), color);
-                return Some((start, dir));
+                let row = diag.min(last_row) - idx;
+                let column = diag - row;
+                return Some((Location { row, column }, dir));
             }
         }
+        // diag_down_right[diag] holds the cells where last_row + column - row == diag, starting
+        // from the top-most one.
         for (diag, group) in self.diag_down_right.iter().enumerate() {
-            if let Some((idx, is_forward)) = find_in_group(word, group) {
+            if let Some((idx, is_forward)) =
+                find_in_group(&letters, &reversed, group, false, case_insensitive)
+            {
                 let dir = if is_forward {
                     Direction::AngledDownRight
                 } else {
                     Direction::AngledUpLeft
                 };
-                let row: usize;
-                let column: usize;
-                if diag < num_rows {
-                    row = num_rows - diag + idx - 1;
-                    column = idx;
-                } else {
-                    row = idx;
-                    column = diag - num_rows + idx + 1;
-                }
-                let start = Location { row, column };
-                self.highlight(&start, &dir, word.len(), color);
-                return Some((start, dir));
+                let row = last_row.saturating_sub(diag) + idx;
+                let column = diag.saturating_sub(last_row) + idx;
+                return Some((Location { row, column }, dir));
             }
         }
 
@@ -254,22 +1225,275 @@ impl Grid {
     }
 }
 
-fn find_in_group(word: &str, group: &Vec<char>) -> Option<(usize, bool)> {
-    let search_text: String = group.iter().collect();
-    if let Some(pos) = search_text.find(word) {
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct GridData {
+    rows: Vec<String>,
+    #[serde(default)]
+    colors: Vec<Vec<Vec<Color>>>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Grid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GridData {
+            rows: self.rows.iter().map(|line| line.iter().collect()).collect(),
+            colors: self.marks.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Grid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let data = GridData::deserialize(deserializer)?;
+        let mut grid = Grid::try_new(data.rows.iter().map(|line| line.as_str()).collect())
+            .map_err(D::Error::custom)?;
+        for (row, line) in data.colors.iter().enumerate() {
+            for (col, colors) in line.iter().enumerate() {
+                if !colors.is_empty() && grid.get(&Location { row, column: col }).is_none() {
+                    return Err(D::Error::custom("highlight outside of the grid"));
+                }
+                for color in colors {
+                    grid.paint(row, col, color);
+                }
+            }
+        }
+        Ok(grid)
+    }
+}
+
+impl FromStr for Grid {
+    type Err = GridError;
+
+    fn from_str(text: &str) -> Result<Self, GridError> {
+        let text = text.to_string().replace(" ", "");
+        let text: Vec<&str> = text.lines().collect();
+        Grid::try_new(text)
+    }
+}
+
+impl std::fmt::Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.rows {
+            for letter in line {
+                write!(f, "{} ", letter)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn xml_escape(letter: char) -> String {
+    match letter {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '"' => "&quot;".to_string(),
+        '\'' => "&apos;".to_string(),
+        _ => letter.to_string(),
+    }
+}
+
+fn place_words(
+    words: &[&str],
+    rows: usize,
+    cols: usize,
+    rng: &mut impl Rng,
+) -> Result<Vec<Vec<Option<char>>>, GenerateError> {
+    if rows == 0 || cols == 0 {
+        return Err(GenerateError::EmptyGrid);
+    }
+    let mut layout = vec![vec![None; cols]; rows];
+    for word in words {
+        let letters: Vec<char> = word.chars().collect();
+        if letters.len() > rows.max(cols) {
+            return Err(GenerateError::WordTooLong(word.to_string()));
+        }
+        let placed = (0..PLACEMENT_ATTEMPTS).any(|_| {
+            let dir = ALL_DIRECTIONS[rng.gen_range(0..ALL_DIRECTIONS.len())];
+            let start = Location {
+                row: rng.gen_range(0..rows),
+                column: rng.gen_range(0..cols),
+            };
+            try_place(&mut layout, &letters, &start, &dir)
+        });
+        if !placed {
+            return Err(GenerateError::NoRoom(word.to_string()));
+        }
+    }
+    Ok(layout)
+}
+
+/// Writes the letters into the layout if every cell is in bounds and empty or already
+/// holds the same letter.
+fn try_place(
+    layout: &mut [Vec<Option<char>>],
+    letters: &[char],
+    start: &Location,
+    dir: &Direction,
+) -> bool {
+    let (row_off, col_off) = dir.delta();
+    let mut cells = Vec::with_capacity(letters.len());
+    for (idx, letter) in letters.iter().enumerate() {
+        let row = start.row as i32 + idx as i32 * row_off;
+        let col = start.column as i32 + idx as i32 * col_off;
+        let (Ok(row), Ok(col)) = (usize::try_from(row), usize::try_from(col)) else {
+            return false;
+        };
+        match layout.get(row).and_then(|line| line.get(col)) {
+            Some(None) => cells.push((row, col)),
+            Some(Some(existing)) if existing == letter => cells.push((row, col)),
+            _ => return false,
+        }
+    }
+    for ((row, col), letter) in cells.into_iter().zip(letters) {
+        layout[row][col] = Some(*letter);
+    }
+    true
+}
+
+fn grid_from_layout(layout: &[Vec<Option<char>>], blank: char) -> Grid {
+    let text: Vec<String> = layout
+        .iter()
+        .map(|line| line.iter().map(|cell| cell.unwrap_or(blank)).collect())
+        .collect();
+    Grid::new(text.iter().map(|line| line.as_str()).collect())
+}
+
+fn is_forward(dir: &Direction) -> bool {
+    matches!(
+        dir,
+        Direction::Right | Direction::Down | Direction::AngledUpRight | Direction::AngledDownRight
+    )
+}
+
+/// `reversed` is `word` back to front. Matching it forwards finds the word read backwards
+/// without building a reversed copy of every group.
+fn find_in_group(
+    word: &[char],
+    reversed: &[char],
+    group: &[char],
+    wrap: bool,
+    case_insensitive: bool,
+) -> Option<(usize, bool)> {
+    if word.is_empty() {
+        return None;
+    }
+    if let Some(pos) = window_starts(group.len(), word.len(), wrap)
+        .find(|&pos| window_matches(group, pos, word, case_insensitive))
+    {
         return Some((pos, true));
     };
-    let reverse: String = group.iter().rev().collect();
-    if let Some(pos) = reverse.find(word) {
-        let last = reverse.len() - 1;
-        return Some((last - pos, false));
+    if let Some(pos) = window_starts(group.len(), word.len(), wrap)
+        .rev()
+        .find(|&pos| window_matches(group, pos, reversed, case_insensitive))
+    {
+        return Some((pos + word.len() - 1, false));
     };
     None
 }
 
+/// Where a `len`-letter window can start in a group of `group_len` letters. With `wrap`, a
+/// window may run off the end and back to the start, but never uses a cell twice.
+fn window_starts(group_len: usize, len: usize, wrap: bool) -> std::ops::Range<usize> {
+    if len > group_len {
+        0..0
+    } else if wrap && len > 1 {
+        0..group_len
+    } else {
+        0..group_len - len + 1
+    }
+}
+
+/// Whether the window at `pos`, read with wrap-around, spells `target`.
+fn window_matches(group: &[char], pos: usize, target: &[char], case_insensitive: bool) -> bool {
+    target
+        .iter()
+        .enumerate()
+        .all(|(i, letter)| letters_match(group[(pos + i) % group.len()], *letter, case_insensitive))
+}
+
+fn letters_match(cell: char, letter: char, case_insensitive: bool) -> bool {
+    if case_insensitive {
+        cell.to_lowercase().eq(letter.to_lowercase())
+    } else {
+        cell == letter
+    }
+}
+
+pub fn render_markdown(grid: &Grid, words: &[&str]) -> String {
+    render_markdown_with_lang(grid, words, "text")
+}
+
+pub fn render_markdown_with_lang(grid: &Grid, words: &[&str], lang: &str) -> String {
+    let mut out = format!("```{lang}\n{grid}```\n\n");
+    for word in words {
+        out.push_str(&format!("- {word}\n"));
+    }
+    out
+}
+
+/// Compares the found words of two solutions by word: words only `b` found are added, words only
+/// `a` found are removed, and words found at different placements moved. A word listed twice is
+/// compared by its first placement.
+pub fn diff_solutions(a: &SolveReport, b: &SolveReport) -> SolutionDiff {
+    let first_placements = |report: &SolveReport| {
+        let mut seen: Vec<(String, Placement)> = Vec::new();
+        for (word, start, dir) in &report.found {
+            if !seen.iter().any(|(other, _)| other == word) {
+                seen.push((word.clone(), (*start, *dir)));
+            }
+        }
+        seen
+    };
+    let (before, after) = (first_placements(a), first_placements(b));
+    let placement_in = |placements: &[(String, Placement)], word: &str| {
+        placements
+            .iter()
+            .find(|(other, _)| other == word)
+            .map(|(_, placement)| *placement)
+    };
+    let mut diff = SolutionDiff::default();
+    for (word, (start, dir)) in &after {
+        match placement_in(&before, word) {
+            None => diff.added.push((word.clone(), *start, *dir)),
+            Some(old) if old != (*start, *dir) => {
+                diff.moved.push((word.clone(), old, (*start, *dir)))
+            }
+            Some(_) => {}
+        }
+    }
+    for (word, (start, dir)) in &before {
+        if placement_in(&after, word).is_none() {
+            diff.removed.push((word.clone(), *start, *dir));
+        }
+    }
+    diff
+}
+
+/// A color for each word, the same for the same seed. Consecutive words never share a color,
+/// and `Color::Reset` is never chosen. Only the seed and the number of words matter, not the
+/// words themselves: with a given seed the nth word always gets the same color.
+pub fn assign_colors(words: &[String], seed: u64) -> Vec<Color> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut colors: Vec<Color> = Vec::with_capacity(words.len());
+    for _ in words {
+        let mut color: Color = rng.gen();
+        while colors.last() == Some(&color) {
+            color = rng.gen();
+        }
+        colors.push(color);
+    }
+    colors
+}
+
 pub fn get_words(text: &str) -> Vec<String> {
     let re = Regex::new(r"\s+").unwrap();
-    let lines = re.split(&text);
+    let lines = re.split(text);
     let mut res = Vec::new();
     for line in lines {
         if line.is_empty() {
@@ -280,8 +1504,892 @@ pub fn get_words(text: &str) -> Vec<String> {
     res
 }
 
-pub fn read_file(file: &str) -> (Grid, Vec<String>) {
-    let text = fs::read_to_string(file).expect("Error reading the file");
-    let text: Vec<&str> = text.split("\r\n\r\n\r\n").collect();
-    (Grid::from_str(text[0]), get_words(text[1]))
+#[derive(Debug)]
+pub enum ReadError {
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    NotUtf8 {
+        path: String,
+        source: std::string::FromUtf8Error,
+    },
+    InvalidGrid {
+        path: String,
+        source: GridError,
+    },
+}
+
+impl std::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadError::Io { path, source } => write!(f, "Error reading {path}: {source}"),
+            ReadError::NotUtf8 { path, source } => write!(
+                f,
+                "{path} is not valid UTF-8 text ({source}). \
+                 Is it a binary file (PDF, image) or saved in a different encoding?"
+            ),
+            ReadError::InvalidGrid { path, source } => {
+                write!(f, "The grid in {path} is invalid: {source}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io { source, .. } => Some(source),
+            ReadError::NotUtf8 { source, .. } => Some(source),
+            ReadError::InvalidGrid { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Concatenated gzip members are read one after another.
+#[cfg(feature = "gzip")]
+fn gunzip(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+    let mut out = Vec::new();
+    flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+/// Like `get_words`, but drops words shorter than `min_len` letters and, if `alpha_only` is set,
+/// any word containing something other than letters.
+pub fn get_words_filtered(text: &str, min_len: usize, alpha_only: bool) -> Vec<String> {
+    get_words(text)
+        .into_iter()
+        .filter(|word| word.chars().count() >= min_len)
+        .filter(|word| !alpha_only || word.chars().all(char::is_alphabetic))
+        .collect()
+}
+
+pub fn read_file(file: &str) -> Result<(Grid, Vec<String>), ReadError> {
+    let text = read_text(file)?;
+    parse_puzzle(file, &text)
+}
+
+/// Reads a word list on its own, gzipped or with any line endings just like a puzzle file.
+pub fn read_words(file: &str) -> Result<Vec<String>, ReadError> {
+    read_text(file).map(|text| get_words(&text))
+}
+
+/// Reads a file holding several puzzles, each laid out as for `read_file`. Puzzles are separated
+/// by a line of `---` or by a form feed. Blank blocks, such as one after a trailing separator,
+/// are skipped.
+pub fn read_file_multi(file: &str) -> Result<Vec<(Grid, Vec<String>)>, ReadError> {
+    let text = read_text(file)?;
+    let mut blocks = vec![String::new()];
+    for line in text.split_inclusive('\n') {
+        for (idx, part) in line.split('\x0c').enumerate() {
+            if idx > 0 || part.trim() == "---" {
+                blocks.push(String::new());
+            }
+            if part.trim() != "---" {
+                blocks.last_mut().unwrap().push_str(part);
+            }
+        }
+    }
+    blocks
+        .iter()
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| parse_puzzle(file, block.trim_matches('\n')))
+        .collect()
+}
+
+fn read_text(file: &str) -> Result<String, ReadError> {
+    let bytes = fs::read(file).map_err(|source| ReadError::Io {
+        path: file.to_string(),
+        source,
+    })?;
+    #[cfg(feature = "gzip")]
+    let bytes = if file.ends_with(".gz") || bytes.starts_with(&[0x1f, 0x8b]) {
+        gunzip(&bytes).map_err(|source| ReadError::Io {
+            path: file.to_string(),
+            source,
+        })?
+    } else {
+        bytes
+    };
+    let text = String::from_utf8(bytes).map_err(|source| ReadError::NotUtf8 {
+        path: file.to_string(),
+        source,
+    })?;
+    Ok(text.replace("\r\n", "\n"))
+}
+
+fn parse_puzzle(file: &str, text: &str) -> Result<(Grid, Vec<String>), ReadError> {
+    let text: Vec<&str> = text.split("\n\n\n").collect();
+    let grid = Grid::from_str(text[0]).map_err(|source| ReadError::InvalidGrid {
+        path: file.to_string(),
+        source,
+    })?;
+    let words = text.get(1).map_or_else(Vec::new, |words| get_words(words));
+    Ok((grid, words))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_between_locations() {
+        let a = Location::new(1, 1);
+        let b = Location::new(4, 3);
+        assert_eq!(a.manhattan(&b), 5);
+        assert_eq!(a.chebyshev(&b), 3);
+        assert_eq!(b.manhattan(&a), 5);
+        assert_eq!(a.chebyshev(&a), 0);
+    }
+
+    #[test]
+    fn direction_to_colinear_cells() {
+        let center = Location::new(3, 3);
+        let cases = [
+            (Location::new(0, 3), Direction::Up),
+            (Location::new(5, 3), Direction::Down),
+            (Location::new(3, 0), Direction::Left),
+            (Location::new(3, 6), Direction::Right),
+            (Location::new(1, 5), Direction::AngledUpRight),
+            (Location::new(4, 4), Direction::AngledDownRight),
+            (Location::new(0, 0), Direction::AngledUpLeft),
+            (Location::new(6, 0), Direction::AngledDownLeft),
+        ];
+        for (other, dir) in cases {
+            assert_eq!(center.direction_to(&other), Some(dir));
+        }
+    }
+
+    #[test]
+    fn direction_to_non_colinear_cells() {
+        let center = Location::new(3, 3);
+        assert_eq!(center.direction_to(&center), None);
+        assert_eq!(center.direction_to(&Location::new(4, 5)), None);
+        assert_eq!(center.direction_to(&Location::new(0, 4)), None);
+    }
+
+    fn temp_file(name: &str, bytes: &[u8]) -> String {
+        let path = std::env::temp_dir().join(format!("finder-{}-{name}", std::process::id()));
+        fs::write(&path, bytes).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    #[cfg(feature = "gzip")]
+    fn gzip(text: &str) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn reads_gzipped_puzzle() {
+        let path = temp_file("puzzle.txt.gz", &gzip("CAT\nXOX\n\n\nCAT COW\n"));
+        let (grid, words) = read_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(grid.to_string(), "C A T \nX O X \n");
+        assert_eq!(words, ["CAT", "COW"]);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn reads_concatenated_gzip_members() {
+        let mut bytes = gzip("CAT\nXOX\n");
+        bytes.extend(gzip("\n\nCAT COW\n"));
+        let path = temp_file("members.gz", &bytes);
+        let (grid, words) = read_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(grid.dimensions(), (2, 3));
+        assert_eq!(words, ["CAT", "COW"]);
+    }
+
+    #[test]
+    fn report_in_reading_order() {
+        let mut grid = Grid::from_str("XXDOG\r\nCATXX\r\nXXXXX").unwrap();
+        let words: Vec<String> = ["CAT", "GOD", "XX"].map(String::from).into();
+        let report = grid.solve_report(&words);
+        let starts: Vec<_> = report
+            .in_reading_order()
+            .iter()
+            .map(|(word, start, _)| (word.as_str(), *start))
+            .collect();
+        assert_eq!(
+            starts,
+            [
+                ("XX", Location::new(0, 0)),
+                ("GOD", Location::new(0, 4)),
+                ("CAT", Location::new(1, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_known_anagram_placements() {
+        let grid = Grid::from_str("TACX\r\nXXXX\r\nXXXA").unwrap();
+        assert_eq!(
+            grid.find_anagram("CAT"),
+            [(Location::new(0, 0), Direction::Right)]
+        );
+        assert!(grid.find_anagram("DOG").is_empty());
+    }
+
+    #[test]
+    fn single_letter_anagram_is_reported_once_per_cell() {
+        let grid = Grid::from_str("TACX\r\nXXXX\r\nXXXA").unwrap();
+        assert_eq!(
+            grid.find_anagram("A"),
+            [
+                (Location::new(0, 1), Direction::Right),
+                (Location::new(2, 3), Direction::Right),
+            ]
+        );
+    }
+
+    #[test]
+    fn bounding_box_of_found_words() {
+        let mut grid = Grid::from_str("XXXXX\r\nXXGOD\r\nXXXAX\r\nXXCXX").unwrap();
+        let words: Vec<String> = ["GOD", "CAD", "DOG", "NOPE"].map(String::from).into();
+        let report = grid.solve_report(&words);
+        assert_eq!(
+            report.bounding_box(),
+            Some((Location::new(1, 2), Location::new(3, 4)))
+        );
+        assert_eq!(SolveReport::default().bounding_box(), None);
+    }
+
+    #[test]
+    fn scan_banned_ignores_case() {
+        let grid = Grid::from_str("XCATX\r\nXXXXX\r\nGODXX").unwrap();
+        let banned: HashSet<String> = ["cat", "Dog", "rat"].map(String::from).into();
+        assert_eq!(
+            grid.scan_banned(&banned),
+            [
+                (
+                    "Dog".to_string(),
+                    "DOG".to_string(),
+                    Location::new(2, 2),
+                    Direction::Left
+                ),
+                (
+                    "cat".to_string(),
+                    "CAT".to_string(),
+                    Location::new(0, 1),
+                    Direction::Right
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn resize_grows_and_keeps_diagonals() {
+        let mut grid = Grid::from_str("AB\r\nCD").unwrap();
+        grid.resize(3, 4, 'X').unwrap();
+        assert_eq!(grid.to_string(), "A B X X \nC D X X \nX X X X \n");
+        assert_eq!(
+            grid.locate("ADX"),
+            Some((Location::new(0, 0), Direction::AngledDownRight))
+        );
+        assert_eq!(
+            grid.locate("XDA"),
+            Some((Location::new(2, 2), Direction::AngledUpLeft))
+        );
+        assert_eq!(
+            grid.locate("CB"),
+            Some((Location::new(1, 0), Direction::AngledUpRight))
+        );
+        assert_eq!(
+            grid.locate("BC"),
+            Some((Location::new(0, 1), Direction::AngledDownLeft))
+        );
+    }
+
+    #[test]
+    fn resize_shrinks_and_keeps_diagonals() {
+        let mut grid = Grid::from_str("ABC\r\nDEF\r\nGHI").unwrap();
+        grid.resize(2, 2, 'X').unwrap();
+        assert_eq!(grid.to_string(), "A B \nD E \n");
+        assert_eq!(
+            grid.locate("AE"),
+            Some((Location::new(0, 0), Direction::AngledDownRight))
+        );
+        assert_eq!(
+            grid.locate("DB"),
+            Some((Location::new(1, 0), Direction::AngledUpRight))
+        );
+        assert_eq!(grid.locate("EI"), None);
+    }
+
+    #[test]
+    fn resize_to_nothing_is_an_error() {
+        let mut grid = Grid::from_str("AB\r\nCD").unwrap();
+        assert_eq!(grid.resize(0, 3, 'X'), Err(GridError::Empty));
+        assert_eq!(grid.resize(3, 0, 'X'), Err(GridError::Empty));
+        assert_eq!(grid.dimensions(), (2, 2));
+    }
+
+    #[test]
+    fn generated_filler_stays_in_alphabet() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let grid = Grid::generate_with_alphabet(&["AÑO"], 6, 6, "ÑÜ", &mut rng)
+            .unwrap()
+            .puzzle;
+        let letters: String = grid.to_string().split_whitespace().collect();
+        assert!(letters.chars().all(|letter| "AOÑÜ".contains(letter)));
+        assert!(grid.locate("AÑO").is_some());
+        assert_eq!(
+            Grid::generate_with_alphabet(&["AÑO"], 6, 6, "", &mut rng).err(),
+            Some(GenerateError::EmptyAlphabet)
+        );
+    }
+
+    #[test]
+    fn finds_accented_word_on_a_diagonal() {
+        let mut grid = Grid::from_str("ÉXXXXÑ\r\nXCXXXÜ\r\nXXOXXX\r\nXXXLXX\r\nXXXXEX").unwrap();
+        assert_eq!(
+            grid.find_word("ÉCOLE", &Color::Red),
+            Some((Location::new(0, 0), Direction::AngledDownRight))
+        );
+        for idx in 0..5 {
+            assert_eq!(grid.colors_at(&Location::new(idx, idx)), [Color::Red]);
+        }
+        assert!(grid.colors_at(&Location::new(0, 5)).is_empty());
+        assert_eq!(
+            grid.find_word("ELOCÉ", &Color::Green),
+            Some((Location::new(4, 4), Direction::AngledUpLeft))
+        );
+        assert_eq!(
+            grid.find_word("ÜÑ", &Color::Green),
+            Some((Location::new(1, 5), Direction::Up))
+        );
+    }
+
+    #[test]
+    fn get_words_drops_carriage_returns() {
+        assert_eq!(get_words("CAT\r\nDOG\r\n"), ["CAT", "DOG"]);
+    }
+
+    #[test]
+    fn reads_any_line_endings() {
+        let puzzle = "CAT\nXOX\nDOG\n\n\nCAT DOG\nCOW\n";
+        let mixed = "CAT\r\nXOX\nDOG\r\n\n\r\nCAT DOG\nCOW\r\n";
+        let mut read = Vec::new();
+        for (name, text) in [
+            ("lf.txt", puzzle.to_string()),
+            ("crlf.txt", puzzle.replace('\n', "\r\n")),
+            ("mixed.txt", mixed.to_string()),
+        ] {
+            let path = temp_file(name, text.as_bytes());
+            let (grid, words) = read_file(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+            read.push((grid.to_string(), words));
+        }
+        assert_eq!(read[0].0, "C A T \nX O X \nD O G \n");
+        assert_eq!(read[0].1, ["CAT", "DOG", "COW"]);
+        assert_eq!(read[1], read[0]);
+        assert_eq!(read[2], read[0]);
+    }
+
+    #[test]
+    fn generated_puzzle_contains_every_word() {
+        let words = ["RUST", "GRID", "WORD", "SEARCH", "CRATE", "LETTER"];
+        for seed in 0..50 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let Generated { puzzle, solution } = Grid::generate(&words, 7, 9, &mut rng).unwrap();
+            assert_eq!(puzzle.dimensions(), (7, 9));
+            for word in words {
+                let (start, dir) = puzzle.locate(word).unwrap();
+                for idx in 0..word.len() {
+                    let cell = start.step(&dir, idx).unwrap();
+                    assert_eq!(
+                        puzzle.get(&cell),
+                        solution.get(&cell),
+                        "{word}, seed {seed}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_keeps_letters_and_highlights() {
+        let mut grid = Grid::from_str("CATX\r\nXOXX\r\nXXWX").unwrap();
+        grid.find_word("CAT", &Color::Red);
+        let json = serde_json::to_string(&grid).unwrap();
+        let mut restored: Grid = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.to_string(), grid.to_string());
+        assert_eq!(restored.colors_at(&Location::new(0, 1)), [Color::Red]);
+        assert!(restored.colors_at(&Location::new(1, 1)).is_empty());
+        assert_eq!(
+            restored.find_word("COW", &Color::Green),
+            Some((Location::new(0, 0), Direction::AngledDownRight))
+        );
+    }
+
+    #[test]
+    fn end_of_a_word() {
+        let start = Location::new(2, 1);
+        let end = start.end(&Direction::Right, 4).unwrap();
+        assert_eq!(end, Location::new(2, 4));
+        assert_eq!((end.row(), end.column()), (2, 4));
+        assert_eq!(
+            start.end(&Direction::AngledUpLeft, 2),
+            Some(Location::new(1, 0))
+        );
+        assert_eq!(start.end(&Direction::Left, 3), None);
+    }
+
+    #[test]
+    fn highlight_off_the_grid_does_nothing() {
+        let mut grid = Grid::from_str("CAT\r\nXXX").unwrap();
+        assert!(!grid.highlight(&Location::new(0, 0), &Direction::Left, 3, &Color::Red));
+        assert!(!grid.highlight(&Location::new(1, 1), &Direction::Down, 2, &Color::Red));
+        assert!(!grid.highlight(&Location::new(5, 0), &Direction::Right, 1, &Color::Red));
+        assert!((0..3).all(|col| grid.colors_at(&Location::new(0, col)).is_empty()));
+        assert!(grid.highlight(&Location::new(0, 2), &Direction::Left, 3, &Color::Red));
+        assert_eq!(grid.colors_at(&Location::new(0, 0)), [Color::Red]);
+        grid.set_wrap(true);
+        assert!(grid.highlight(&Location::new(1, 1), &Direction::Down, 2, &Color::Green));
+        assert_eq!(
+            grid.colors_at(&Location::new(0, 1)),
+            [Color::Red, Color::Green]
+        );
+    }
+
+    #[test]
+    fn wrap_applies_to_every_search() {
+        let mut grid = Grid::from_str("ABCD\r\nXXXX\r\nXXXX").unwrap();
+        let words = ["CDA".to_string()];
+        assert_eq!(grid.locate("CDA"), None);
+        assert_eq!(grid.count_occurrences("CDA"), 0);
+        grid.set_wrap(true);
+        let expected = Some((Location::new(0, 2), Direction::Right));
+        assert_eq!(grid.locate("CDA"), expected);
+        assert_eq!(grid.count_occurrences("CDA"), 1);
+        assert_eq!(grid.find_horizontal_forward("CDA"), expected);
+        assert!(grid.is_fully_solvable(&["CDA"]));
+        assert!(grid.validate_unique(&words).is_empty());
+        assert_eq!(
+            grid.find_word_with("CDA", &Color::Red, &TieBreak::PreferForward),
+            expected
+        );
+        assert_eq!(grid.colors_at(&Location::new(0, 0)), [Color::Red]);
+        assert!(grid.colors_at(&Location::new(0, 1)).is_empty());
+        assert_eq!(
+            grid.locate("AXX"),
+            Some((Location::new(0, 0), Direction::Down))
+        );
+        assert_eq!(
+            grid.locate("XXA"),
+            Some((Location::new(1, 0), Direction::Down))
+        );
+        assert_eq!(grid.count_occurrences("ABCDA"), 0);
+        assert_eq!(grid.count_occurrences("DX"), 3);
+    }
+
+    fn assert_starts(text: &str, cases: &[(&str, (usize, usize), Direction)]) {
+        let grid = Grid::from_str(text).unwrap();
+        for &(word, (row, column), dir) in cases {
+            assert_eq!(
+                grid.locate(word),
+                Some((Location::new(row, column), dir)),
+                "{word}"
+            );
+        }
+    }
+
+    #[test]
+    fn diagonal_starts_on_a_wide_grid() {
+        assert_starts(
+            "ABCDE\r\nFGHIJ\r\nKLMNO",
+            &[
+                ("FB", (1, 0), Direction::AngledUpRight),
+                ("KGC", (2, 0), Direction::AngledUpRight),
+                ("MIE", (2, 2), Direction::AngledUpRight),
+                ("BF", (0, 1), Direction::AngledDownLeft),
+                ("CGK", (0, 2), Direction::AngledDownLeft),
+                ("EIM", (0, 4), Direction::AngledDownLeft),
+                ("FL", (1, 0), Direction::AngledDownRight),
+                ("AGM", (0, 0), Direction::AngledDownRight),
+                ("CIO", (0, 2), Direction::AngledDownRight),
+                ("LF", (2, 1), Direction::AngledUpLeft),
+                ("MGA", (2, 2), Direction::AngledUpLeft),
+                ("OIC", (2, 4), Direction::AngledUpLeft),
+            ],
+        );
+    }
+
+    #[test]
+    fn diagonal_starts_on_a_tall_grid() {
+        assert_starts(
+            "ABC\r\nDEF\r\nGHI\r\nJKL\r\nMNO",
+            &[
+                ("DB", (1, 0), Direction::AngledUpRight),
+                ("GEC", (2, 0), Direction::AngledUpRight),
+                ("MKI", (4, 0), Direction::AngledUpRight),
+                ("BD", (0, 1), Direction::AngledDownLeft),
+                ("CEG", (0, 2), Direction::AngledDownLeft),
+                ("IKM", (2, 2), Direction::AngledDownLeft),
+                ("BF", (0, 1), Direction::AngledDownRight),
+                ("AEI", (0, 0), Direction::AngledDownRight),
+                ("DHL", (1, 0), Direction::AngledDownRight),
+                ("JN", (3, 0), Direction::AngledDownRight),
+                ("IEA", (2, 2), Direction::AngledUpLeft),
+                ("LHD", (3, 2), Direction::AngledUpLeft),
+                ("NJ", (4, 1), Direction::AngledUpLeft),
+            ],
+        );
+    }
+
+    #[test]
+    fn finds_words_with_a_stride() {
+        let mut grid = Grid::from_str("CXAXTX\r\nXXXXXX\r\nDOGXXX").unwrap();
+        assert_eq!(
+            grid.find_word_stride("CAT", 2, &Color::Red),
+            Some((Location::new(0, 0), Direction::Right))
+        );
+        assert_eq!(
+            (0..6)
+                .filter(|&col| !grid.colors_at(&Location::new(0, col)).is_empty())
+                .collect::<Vec<_>>(),
+            [0, 2, 4]
+        );
+        assert_eq!(
+            grid.find_word_stride("TAC", 2, &Color::Red),
+            Some((Location::new(0, 4), Direction::Left))
+        );
+        assert_eq!(grid.find_word_stride("CAT", 3, &Color::Red), None);
+        assert_eq!(grid.find_word_stride("CAT", 0, &Color::Red), None);
+
+        let mut plain = Grid::from_str("CXAXTX\r\nXXXXXX\r\nDOGXXX").unwrap();
+        for word in ["DOG", "GOD", "XXX", "CAT"] {
+            assert_eq!(
+                grid.find_word_stride(word, 1, &Color::Green),
+                plain.find_word(word, &Color::Green)
+            );
+        }
+    }
+
+    #[test]
+    fn crossing_words_keep_every_highlight() {
+        let mut grid = Grid::from_str("CAT\r\nXOX\r\nXWX").unwrap();
+        grid.find_word("CAT", &Color::Red);
+        grid.find_word("AOW", &Color::Red);
+        assert_eq!(
+            grid.colors_at(&Location::new(0, 1)),
+            [Color::Red, Color::Red]
+        );
+        assert_eq!(grid.colors_at(&Location::new(1, 1)), [Color::Red]);
+        let underlined = format!("{}{}A{}", Color::Red, UNDERLINE, Color::Reset);
+        assert!(grid.solve_string().contains(&underlined));
+    }
+
+    #[test]
+    fn solve_report_records_colors() {
+        let words: Vec<String> = ["CAT", "NOPE", "COW"].map(String::from).into();
+        let mut grid = Grid::from_str("CATX\r\nOXXX\r\nWXXX").unwrap();
+        let report = grid.solve_report_seeded(&words, 3);
+        let colors = assign_colors(&words, 3);
+        assert_eq!(report.colors, [colors[0], colors[2]]);
+        assert_eq!(report.not_found, ["NOPE"]);
+        assert_eq!(grid.colors_at(&Location::new(2, 0)), [colors[2]]);
+    }
+
+    #[test]
+    fn overlap_priority_flips_the_winner() {
+        let words: Vec<String> = ["COW", "CATS"].map(String::from).into();
+        let colors = assign_colors(&words, DEFAULT_COLOR_SEED);
+        let corner = Location::new(0, 0);
+
+        let mut grid = Grid::from_str("CATS\r\nOXXX\r\nWXXX").unwrap();
+        let report = grid.solve_report(&words);
+        assert_eq!(grid.colors_at(&corner)[0], colors[0]);
+        assert_eq!(report.found[0].0, "COW");
+
+        let mut grid = Grid::from_str("CATS\r\nOXXX\r\nWXXX").unwrap();
+        let report =
+            grid.solve_report_with_priority(&words, |(word, _, _)| word.chars().count() as i32);
+        assert_eq!(grid.colors_at(&corner)[0], colors[1]);
+        assert_eq!(report.found[0].0, "CATS");
+        assert_eq!(report.colors, [colors[1], colors[0]]);
+    }
+
+    #[test]
+    fn legend_lists_found_words_in_their_colors() {
+        let words: Vec<String> = ["CAT", "NOPE", "COW"].map(String::from).into();
+        let mut grid = Grid::from_str("CATX\r\nOXXX\r\nWXXX").unwrap();
+        let report = grid.solve_report(&words);
+        assert_eq!(
+            report.legend(ColorMode::Never),
+            "CAT at 0, 0 going LEFT to RIGHT.\nCOW at 0, 0 going DOWN.\n"
+        );
+        let colored = report.legend(ColorMode::Always);
+        assert!(colored.starts_with(&format!("{}CAT{} at 0, 0", report.colors[0], Color::Reset)));
+        assert!(colored.contains(&format!("{}COW{}", report.colors[1], Color::Reset)));
+    }
+
+    #[test]
+    fn diff_between_two_solutions() {
+        let words: Vec<String> = ["CAT", "DOG", "COW"].map(String::from).into();
+        let before = Grid::from_str("CATX\r\nOXXX\r\nWDOG")
+            .unwrap()
+            .solve_report(&words);
+        let words: Vec<String> = ["CAT", "DOG", "EMU"].map(String::from).into();
+        let after = Grid::from_str("XXXX\r\nCATX\r\nEMUX\r\nDOGX")
+            .unwrap()
+            .solve_report(&words);
+        let diff = diff_solutions(&before, &after);
+        assert_eq!(
+            diff.added,
+            [("EMU".to_string(), Location::new(2, 0), Direction::Right)]
+        );
+        assert_eq!(
+            diff.removed,
+            [("COW".to_string(), Location::new(0, 0), Direction::Down)]
+        );
+        assert_eq!(
+            diff.moved,
+            [
+                (
+                    "CAT".to_string(),
+                    (Location::new(0, 0), Direction::Right),
+                    (Location::new(1, 0), Direction::Right)
+                ),
+                (
+                    "DOG".to_string(),
+                    (Location::new(2, 1), Direction::Right),
+                    (Location::new(3, 0), Direction::Right)
+                ),
+            ]
+        );
+        assert!(diff
+            .to_string()
+            .starts_with("+ EMU at 2, 0 going LEFT to RIGHT\n"));
+        assert!(diff_solutions(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn solve_stream_yields_in_order() {
+        let grid: Grid = "CATX\nXXXX\nGODX".parse().unwrap();
+        let words = ["DOG", "BIRD", "CAT"].map(String::from);
+        let mut events = grid.solve_stream(words);
+        assert_eq!(
+            events.next(),
+            Some(SolveEvent::Found(
+                "DOG".to_string(),
+                Location::new(2, 2),
+                Direction::Left
+            ))
+        );
+        assert_eq!(events.next(), Some(SolveEvent::Missing("BIRD".to_string())));
+        assert_eq!(
+            events.next(),
+            Some(SolveEvent::Found(
+                "CAT".to_string(),
+                Location::new(0, 0),
+                Direction::Right
+            ))
+        );
+        assert_eq!(events.next(), None);
+        assert!(!grid.is_highlighted(0, 0));
+    }
+
+    #[test]
+    fn palindrome_counts_once() {
+        let grid: Grid = "ABA\nXXX\nXXX".parse().unwrap();
+        assert_eq!(grid.count_occurrences("ABA"), 1);
+    }
+
+    #[test]
+    fn single_letter_counts_once_per_cell() {
+        let grid: Grid = "AB\nBA".parse().unwrap();
+        assert_eq!(grid.count_occurrences("A"), 2);
+        assert_eq!(grid.count_occurrences("B"), 2);
+    }
+
+    #[test]
+    fn validate_unique_reports_missing_and_repeated_words() {
+        let grid: Grid = "ABA\nXXX\nXXX".parse().unwrap();
+        let words = ["ABA", "Q", "X"].map(String::from);
+        assert_eq!(
+            grid.validate_unique(&words),
+            [("Q".to_string(), 0), ("X".to_string(), 6)]
+        );
+    }
+
+    #[test]
+    fn find_all_reports_each_placement_once() {
+        let mut grid: Grid = "ABA\nBXX\nAXX".parse().unwrap();
+        let found = grid.find_all("ABA", &Color::Red);
+        assert_eq!(
+            found,
+            [
+                (Location::new(0, 0), Direction::Right),
+                (Location::new(0, 0), Direction::Down),
+            ]
+        );
+        assert_eq!(
+            grid.colors_at(&Location::new(0, 0)),
+            [Color::Red, Color::Red]
+        );
+        for loc in [(0, 1), (0, 2), (1, 0), (2, 0)] {
+            assert_eq!(grid.colors_at(&Location::new(loc.0, loc.1)), [Color::Red]);
+        }
+        assert!(grid.colors_at(&Location::new(1, 1)).is_empty());
+    }
+
+    #[test]
+    fn read_file_multi_splits_on_every_separator() {
+        let text = "CAT\nXOX\n\n\nCAT\n\n---\n\nDOG\nXXX\n\n\nDOG\n\x0cAB\nCD\n\n\nAB\n---\n\n";
+        let path = temp_file("multi.txt", text.as_bytes());
+        let puzzles = read_file_multi(&path).unwrap();
+        let words: Vec<&[String]> = puzzles.iter().map(|(_, words)| &words[..]).collect();
+        assert_eq!(words, [["CAT"], ["DOG"], ["AB"]]);
+        let firsts: Vec<Option<char>> = puzzles
+            .iter()
+            .map(|(grid, _)| grid.get(&Location::new(1, 0)))
+            .collect();
+        assert_eq!(firsts, [Some('X'), Some('X'), Some('C')]);
+    }
+
+    #[test]
+    fn try_new_rejects_ragged_rows() {
+        assert_eq!(
+            Grid::try_new(vec!["ABC", "DE", "FGH"]).err(),
+            Some(GridError::Ragged {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn try_new_rejects_an_empty_grid() {
+        assert_eq!(Grid::try_new(vec![]).err(), Some(GridError::Empty));
+        assert_eq!(Grid::try_new(vec![""]).err(), Some(GridError::Empty));
+    }
+
+    #[test]
+    fn trailing_blank_line_is_a_ragged_row() {
+        assert_eq!(
+            "ABC\nDEF\n\n".parse::<Grid>().err(),
+            Some(GridError::Ragged {
+                row: 2,
+                expected: 3,
+                found: 0
+            })
+        );
+    }
+
+    #[test]
+    fn from_str_with_round_trips_show_grid() {
+        let grid: Grid = "CAT\nXÖX\nDOG".parse().unwrap();
+        let parsed = Grid::from_str_with(&grid.to_string(), Some(' ')).unwrap();
+        assert_eq!(parsed.to_string(), grid.to_string());
+        assert_eq!(parsed.get(&Location::new(1, 1)), Some('Ö'));
+    }
+
+    #[test]
+    fn from_str_with_rejects_wide_cells() {
+        assert_eq!(
+            Grid::from_str_with("A B\nC DE", Some(' ')).err(),
+            Some(GridError::WideCell {
+                row: 1,
+                cell: "DE".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn from_str_with_no_separator_keeps_spaces() {
+        let grid = Grid::from_str_with("A B\nCDE", None).unwrap();
+        assert_eq!(grid.dimensions(), (2, 3));
+        assert_eq!(grid.get(&Location::new(0, 1)), Some(' '));
+    }
+
+    #[test]
+    fn tie_break_rules_pick_different_placements() {
+        let text = "XBAXX\nXXXXX\nXAXXX\nXBXXB\nXXXAX";
+        let cases = [
+            (TieBreak::FirstFound, (0, 2), Direction::Left),
+            (TieBreak::PreferForward, (2, 1), Direction::Down),
+            (TieBreak::PreferHorizontal, (0, 2), Direction::Left),
+            (
+                TieBreak::Closest(Location::new(4, 4)),
+                (4, 3),
+                Direction::AngledUpRight,
+            ),
+        ];
+        for (rule, (row, column), dir) in cases {
+            let mut grid: Grid = text.parse().unwrap();
+            let expected = Some((Location::new(row, column), dir));
+            assert_eq!(grid.find_word_with("AB", &Color::Red, &rule), expected);
+            assert_eq!(grid.colors_at(&Location::new(row, column)), [Color::Red]);
+        }
+    }
+
+    #[test]
+    fn tie_break_fallback_uses_reading_order() {
+        let mut grid: Grid = "AXXX\nXBXA\nXXXB".parse().unwrap();
+        let column = Some((Location::new(1, 3), Direction::Down));
+        let diagonal = Some((Location::new(0, 0), Direction::AngledDownRight));
+        assert_eq!(
+            grid.find_word_with("AB", &Color::Red, &TieBreak::FirstFound),
+            column
+        );
+        assert_eq!(
+            grid.find_word_with("AB", &Color::Red, &TieBreak::PreferHorizontal),
+            diagonal
+        );
+    }
+
+    #[test]
+    fn find_word_ci_highlights_the_original_letters() {
+        let mut grid: Grid = "XÉcOLeX\nXXXXXXX\nXÜXXXXX\nXbXXXXX".parse().unwrap();
+        assert_eq!(
+            grid.find_word_ci("école", &Color::Red),
+            Some((Location::new(0, 1), Direction::Right, "ÉcOLe".to_string()))
+        );
+        for column in 1..6 {
+            assert_eq!(grid.colors_at(&Location::new(0, column)), [Color::Red]);
+        }
+        assert!(grid.colors_at(&Location::new(0, 0)).is_empty());
+        assert!(grid.colors_at(&Location::new(0, 6)).is_empty());
+        assert_eq!(
+            grid.find_word_ci("bü", &Color::Green),
+            Some((Location::new(3, 1), Direction::Up, "bÜ".to_string()))
+        );
+        assert_eq!(grid.colors_at(&Location::new(2, 1)), [Color::Green]);
+    }
+
+    #[test]
+    fn read_words_splits_on_whitespace() {
+        let path = temp_file("words.txt", b"CAT DOG\r\nBIRD\n");
+        assert_eq!(read_words(&path).unwrap(), ["CAT", "DOG", "BIRD"]);
+    }
+
+    /// Solves 200 words against a 50x50 grid five times and prints how long it took. Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn time_solving_a_large_grid() {
+        let mut rng = StdRng::seed_from_u64(261);
+        let words: Vec<String> = (0..200)
+            .map(|_| {
+                let len = rng.gen_range(4..=8);
+                (0..len).map(|_| rng.gen_range('A'..='Z')).collect()
+            })
+            .collect();
+        let placed: Vec<&str> = words.iter().map(String::as_str).collect();
+        let grid = Grid::generate(&placed, 50, 50, &mut rng).unwrap().puzzle;
+        let start = std::time::Instant::now();
+        for _ in 0..5 {
+            for word in &words {
+                assert!(grid.locate(word).is_some());
+            }
+        }
+        println!("Solved 200 words five times in {:?}", start.elapsed());
+    }
 }
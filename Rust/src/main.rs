@@ -1,28 +1,115 @@
-use finder;
-use rand;
+//! Usage:
+//!
+//! ```text
+//! finder PUZZLE            # grid and word list in one file
+//! finder GRID WORDS        # grid and word list in separate files
+//! cat WORDS | finder GRID  # grid file without a word list, words on stdin
+//! ```
+//!
+//! `--seed N` anywhere on the command line picks the highlight colors. The same seed always
+//! gives the same colors, so runs can be reproduced; without it a fixed default is used.
+//!
+//! Words are separated by any whitespace. When the words come from stdin the solution is
+//! shown right away instead of waiting for 'Enter'.
+//!
+//! A PUZZLE file may hold several puzzles separated by a line of `---` or a form feed. Each is
+//! solved in turn. A WORDS file applies to every puzzle; otherwise stdin supplies the words for
+//! any puzzle without its own.
 use std::{
     env,
-    io::{stdin, stdout, Write},
+    io::{stdin, stdout, Read, Write},
+    process,
 };
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let filename = &args[1];
-    let (mut grid, words) = finder::read_file(filename);
+    let mut args: Vec<String> = env::args().collect();
+    let usage = format!("Usage: {} [--seed N] PUZZLE [WORDS]", args[0]);
+    let mut seed = finder::DEFAULT_COLOR_SEED;
+    if let Some(pos) = args.iter().position(|arg| arg == "--seed") {
+        match args.get(pos + 1).map(|value| value.parse()) {
+            Some(Ok(value)) => seed = value,
+            _ => {
+                eprintln!("--seed needs a whole number\n{usage}");
+                process::exit(2);
+            }
+        }
+        args.drain(pos..=pos + 1);
+    }
+    let Some(filename) = args.get(1) else {
+        eprintln!("{usage}");
+        process::exit(2);
+    };
+    let puzzles = match finder::read_file_multi(filename) {
+        Ok(puzzles) if puzzles.is_empty() => {
+            eprintln!("No puzzle found in {filename}");
+            process::exit(1);
+        }
+        Ok(puzzles) => puzzles,
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    };
+    let mut shared_words = None;
+    if let Some(words_file) = args.get(2) {
+        match finder::read_words(words_file) {
+            Ok(words) => shared_words = Some(words),
+            Err(err) => {
+                eprintln!("{err}");
+                process::exit(1);
+            }
+        }
+    }
+    let mut stdin_words: Option<Vec<String>> = None;
+    for (idx, (grid, mut words)) in puzzles.into_iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        if let Some(shared) = &shared_words {
+            words = shared.clone();
+        } else if words.is_empty() {
+            let stdin_words = stdin_words.get_or_insert_with(|| {
+                let mut text = String::new();
+                if let Err(err) = stdin().read_to_string(&mut text) {
+                    eprintln!("Error reading words from stdin: {err}");
+                    process::exit(1);
+                }
+                finder::get_words(&text)
+            });
+            words = stdin_words.clone();
+        }
+        solve(grid, words, stdin_words.is_none(), seed);
+    }
+}
+
+fn solve(mut grid: finder::Grid, words: Vec<String>, prompt: bool, seed: u64) {
     grid.show_grid();
-    println!("Press 'Enter' to reveal solution.");
-    stdout().flush().unwrap();
-    let mut input = String::new();
-    stdin().read_line(&mut input).unwrap();
+    if prompt {
+        println!("Press 'Enter' to reveal solution.");
+        stdout().flush().unwrap();
+        let mut input = String::new();
+        stdin().read_line(&mut input).unwrap();
+    }
+    grid.set_color_mode(finder::ColorMode::Auto);
+    let use_color = finder::ColorMode::Auto.enabled();
     let reset = finder::Color::Reset;
-    for word in words {
-        let color: finder::Color = rand::random();
-        if let Some(found) = grid.find_word(&word, &color) {
-            let (loc, dir) = found;
-            println!("Found {color}{word}{reset} at {loc} going {dir}.")
+    let missing = finder::Color::Red;
+    let report = grid.solve_report_seeded(&words, seed);
+    grid.show_solve();
+    print!("{}", report.legend(finder::ColorMode::Auto));
+    for word in &report.not_found {
+        if use_color {
+            println!("Did not find {missing}{word}{reset}")
         } else {
             println!("Did not find {word}")
         }
     }
-    grid.show_solve();
+    println!(
+        "Found {} of {} words.",
+        report.found_count(),
+        report.total()
+    );
+    if !report.not_found.is_empty() {
+        println!("Missing: {}", report.not_found.join(", "));
+    }
 }